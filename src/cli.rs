@@ -0,0 +1,86 @@
+/*
+* Copyright (C) 2024 Jason Mobarak
+*
+* Contact: Jason Mobarak <git@jason.mobarak.name>
+*
+* This source is subject to the license found in the file 'LICENSE' which must
+* be be distributed together with this source. All other rights reserved.
+*
+* THIS CODE AND INFORMATION IS PROVIDED "AS IS" WITHOUT WARRANTY OF ANY KIND,
+* EITHER EXPRESSED OR IMPLIED, INCLUDING BUT NOT LIMITED TO THE IMPLIED
+* WARRANTIES OF MERCHANTABILITY AND/OR FITNESS FOR A PARTICULAR PURPOSE.
+*/
+
+use std::path::PathBuf;
+
+use clap::Parser;
+
+/// Suffix style used when naming split output files.
+#[derive(clap::ValueEnum, Clone, Copy, Debug, PartialEq, Eq)]
+pub enum SuffixStyle {
+    /// `foo-0.yaml`, `foo-1.yaml`, ... (optionally zero-padded via `--width`).
+    Numeric,
+    /// `foo-aa.yaml`, `foo-ab.yaml`, ...
+    Alpha,
+}
+
+/// Split a multi-document YAML stream into one file per document, or (with
+/// `--join`) do the inverse and merge files back into one stream.
+#[derive(Parser, Debug)]
+#[command(name = "yamlsplit", about = "Split a multi-document YAML stream into one file per document")]
+pub struct Options {
+    /// Input file(s), or "-" for stdin. Splitting only uses the first one;
+    /// `--join` reads every one of them in order, expanding a directory to
+    /// its immediate entries.
+    #[arg(default_value = "-")]
+    pub input: Vec<String>,
+
+    /// Directory to write output files into. Defaults to the input file's
+    /// own directory (the current directory for stdin or `--join`), so
+    /// `yamlsplit sub/foo.yaml` writes `sub/foo-0.yaml` rather than
+    /// scattering output into the caller's working directory.
+    #[arg(long)]
+    pub outdir: Option<PathBuf>,
+
+    /// Override the basename derived from the input file for output names.
+    #[arg(long)]
+    pub prefix: Option<String>,
+
+    /// First index used when naming output files (numeric suffix style only).
+    #[arg(long, default_value_t = 0)]
+    pub start: u32,
+
+    /// Zero-pad numeric suffixes to this width, e.g. `--width 3` => foo-001.yaml.
+    #[arg(long, default_value_t = 0)]
+    pub width: usize,
+
+    /// Suffix style for output filenames.
+    #[arg(long, value_enum, default_value_t = SuffixStyle::Numeric)]
+    pub suffix_style: SuffixStyle,
+
+    /// Name output files from fields inside each document instead of a
+    /// numeric/alpha suffix, e.g. `{kind}-{metadata.name}`.
+    #[arg(long)]
+    pub name_template: Option<String>,
+
+    /// Use the old line-regex matcher instead of the real YAML scanner.
+    #[arg(long)]
+    pub naive: bool,
+
+    /// Join mode: the inverse of splitting. Concatenate the input file(s)
+    /// (or a directory of them) into a single multi-document stream on
+    /// stdout, so `split` then `join` round-trips.
+    #[arg(long)]
+    pub join: bool,
+
+    /// Only emit documents matching a predicate: an index range
+    /// (`2..5`) or a key/value match (`kind=Service`) resolved against the
+    /// parsed document.
+    #[arg(long)]
+    pub select: Option<String>,
+
+    /// With `--select`, write matched documents to stdout instead of
+    /// numbered files.
+    #[arg(long)]
+    pub stdout: bool,
+}
@@ -0,0 +1,156 @@
+/*
+* Copyright (C) 2024 Jason Mobarak
+*
+* Contact: Jason Mobarak <git@jason.mobarak.name>
+*
+* This source is subject to the license found in the file 'LICENSE' which must
+* be be distributed together with this source. All other rights reserved.
+*
+* THIS CODE AND INFORMATION IS PROVIDED "AS IS" WITHOUT WARRANTY OF ANY KIND,
+* EITHER EXPRESSED OR IMPLIED, INCLUDING BUT NOT LIMITED TO THE IMPLIED
+* WARRANTIES OF MERCHANTABILITY AND/OR FITNESS FOR A PARTICULAR PURPOSE.
+*/
+
+//! Compound-extension handling so `cluster.yaml.gz` splits sensibly into a
+//! stem, a data extension, and a compression codec, and so split files can
+//! be transparently read and written through that codec.
+
+use std::io::{Read, Write};
+
+/// A compression codec recognized as a trailing filename suffix.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Compression {
+    Gz,
+    Bz2,
+    Zst,
+}
+
+impl Compression {
+    fn from_ext(ext: &str) -> Option<Self> {
+        match ext {
+            "gz" => Some(Compression::Gz),
+            "bz2" => Some(Compression::Bz2),
+            "zst" => Some(Compression::Zst),
+            _ => None,
+        }
+    }
+
+    fn extension(self) -> &'static str {
+        match self {
+            Compression::Gz => "gz",
+            Compression::Bz2 => "bz2",
+            Compression::Zst => "zst",
+        }
+    }
+}
+
+/// Extensions recognized as YAML/JSON data, as opposed to a compression
+/// suffix layered on top of one.
+const DATA_EXTENSIONS: &[&str] = &["yaml", "yml", "json"];
+
+/// Split `filename` into `(stem, data_extension, compression)`.
+///
+/// A plain last-dot split turns `cluster.yaml.gz` into basename
+/// `cluster.yaml` and extension `gz`, which is wrong: the outputs would be
+/// named `cluster.yaml-0.gz`. This recognizes a trailing compression suffix
+/// (`.gz`, `.bz2`, `.zst`) layered on a known data extension and peels both
+/// off, so `cluster.yaml.gz` becomes `("cluster", "yaml", Some(Gz))`.
+pub fn split_compound_extension(filename: &str) -> (String, String, Option<Compression>) {
+    if let Some((rest, outer_ext)) = filename.rsplit_once('.') {
+        if let Some(compression) = Compression::from_ext(outer_ext) {
+            if let Some((stem, inner_ext)) = rest.rsplit_once('.') {
+                if DATA_EXTENSIONS.contains(&inner_ext) {
+                    return (stem.to_string(), inner_ext.to_string(), Some(compression));
+                }
+            }
+        }
+        return (rest.to_string(), outer_ext.to_string(), None);
+    }
+    (filename.to_string(), String::new(), None)
+}
+
+/// The extension to use for an output filename: `extension` alone, or with
+/// the compression's suffix appended (`yaml.gz`).
+pub fn full_extension(extension: &str, compression: Option<Compression>) -> String {
+    match compression {
+        Some(compression) => format!("{}.{}", extension, compression.extension()),
+        None => extension.to_string(),
+    }
+}
+
+/// Wrap `reader` in a decoder for `compression`, or return it unchanged when
+/// there's nothing to decode.
+pub fn decode_reader(compression: Option<Compression>, reader: Box<dyn Read>) -> Box<dyn Read> {
+    match compression {
+        Some(Compression::Gz) => Box::new(flate2::read::GzDecoder::new(reader)),
+        Some(Compression::Bz2) => Box::new(bzip2::read::BzDecoder::new(reader)),
+        Some(Compression::Zst) => {
+            Box::new(zstd::stream::read::Decoder::new(reader).expect("failed to start zstd decoder"))
+        }
+        None => reader,
+    }
+}
+
+/// Wrap `writer` in an encoder for `compression`, or return it unchanged
+/// when there's nothing to encode. The returned writer finishes the
+/// underlying stream when dropped.
+pub fn encode_writer(compression: Option<Compression>, writer: Box<dyn Write>) -> Box<dyn Write> {
+    match compression {
+        Some(Compression::Gz) => Box::new(flate2::write::GzEncoder::new(
+            writer,
+            flate2::Compression::default(),
+        )),
+        Some(Compression::Bz2) => Box::new(bzip2::write::BzEncoder::new(
+            writer,
+            bzip2::Compression::default(),
+        )),
+        Some(Compression::Zst) => Box::new(
+            zstd::stream::write::Encoder::new(writer, 0)
+                .expect("failed to start zstd encoder")
+                .auto_finish(),
+        ),
+        None => writer,
+    }
+}
+
+#[test]
+fn test_split_compound_extension_plain() {
+    assert_eq!(
+        split_compound_extension("foo.yaml"),
+        ("foo".to_string(), "yaml".to_string(), None)
+    );
+}
+
+#[test]
+fn test_split_compound_extension_no_extension() {
+    assert_eq!(
+        split_compound_extension("foo"),
+        ("foo".to_string(), "".to_string(), None)
+    );
+}
+
+#[test]
+fn test_split_compound_extension_multi_dot_stem() {
+    assert_eq!(
+        split_compound_extension("foo.bar.baz.yaml"),
+        ("foo.bar.baz".to_string(), "yaml".to_string(), None)
+    );
+}
+
+#[test]
+fn test_split_compound_extension_gz() {
+    assert_eq!(
+        split_compound_extension("cluster.yaml.gz"),
+        ("cluster".to_string(), "yaml".to_string(), Some(Compression::Gz))
+    );
+}
+
+#[test]
+fn test_split_compound_extension_compression_suffix_on_unknown_data_extension_left_alone() {
+    // "gz" without a recognized data extension underneath it is just a
+    // two-dot basename, same as any other unrecognized suffix.
+    assert_eq!(
+        split_compound_extension("archive.tar.gz"),
+        ("archive.tar".to_string(), "gz".to_string(), None)
+    );
+}
@@ -0,0 +1,114 @@
+/*
+* Copyright (C) 2024 Jason Mobarak
+*
+* Contact: Jason Mobarak <git@jason.mobarak.name>
+*
+* This source is subject to the license found in the file 'LICENSE' which must
+* be be distributed together with this source. All other rights reserved.
+*
+* THIS CODE AND INFORMATION IS PROVIDED "AS IS" WITHOUT WARRANTY OF ANY KIND,
+* EITHER EXPRESSED OR IMPLIED, INCLUDING BUT NOT LIMITED TO THE IMPLIED
+* WARRANTIES OF MERCHANTABILITY AND/OR FITNESS FOR A PARTICULAR PURPOSE.
+*/
+
+//! `--join` mode: the inverse of splitting. Concatenates multiple YAML
+//! files (or a directory of them) into a single multi-document stream.
+
+use std::io::{Read, Write};
+use std::path::{Path, PathBuf};
+
+use crate::compress;
+use crate::scanner;
+use crate::Result;
+
+/// Expand `inputs` into a flat list of file paths in the order they should
+/// be joined: a directory is expanded to its immediate entries, sorted by
+/// name, while plain files and "-" (stdin) pass through unchanged.
+fn expand_inputs(inputs: &[String]) -> Result<Vec<String>> {
+    let mut expanded = Vec::new();
+    for input in inputs {
+        let path = Path::new(input);
+        if input != "-" && path.is_dir() {
+            let mut entries: Vec<PathBuf> = std::fs::read_dir(path)?
+                .filter_map(|entry| entry.ok())
+                .map(|entry| entry.path())
+                .filter(|p| p.is_file())
+                .collect();
+            entries.sort();
+            expanded.extend(entries.into_iter().map(|p| p.to_string_lossy().into_owned()));
+        } else {
+            expanded.push(input.clone());
+        }
+    }
+    Ok(expanded)
+}
+
+/// Read `path` to a string, transparently decoding it first if its filename
+/// carries a compression suffix (`.gz`, `.bz2`, `.zst`), the same way
+/// `stdin_or_input_file` does for the split path. Stdin is never
+/// decompressed, since there's no filename to recognize a suffix from.
+fn read_input(path: &str) -> Result<String> {
+    let mut text = String::new();
+    if path == "-" {
+        std::io::stdin().read_to_string(&mut text)?;
+        return Ok(text);
+    }
+    let file_name = Path::new(path)
+        .file_name()
+        .and_then(|name| name.to_str())
+        .unwrap_or(path);
+    let (.., compression) = compress::split_compound_extension(file_name);
+    let raw_file = Box::new(std::fs::File::open(path)?) as Box<dyn Read>;
+    compress::decode_reader(compression, raw_file).read_to_string(&mut text)?;
+    Ok(text)
+}
+
+/// Read each of `inputs` in order (expanding directories, see
+/// `expand_inputs`), transparently decompressing each one by its filename
+/// suffix (see `read_input`), and write every document it contains to `out`
+/// as a single multi-document stream: a normalized `---` before each
+/// document and a final `...` terminator, so `split` then `join` round-trips
+/// even when the inputs are the compressed output of a `--outdir` split.
+pub fn run_join(inputs: &[String], out: &mut dyn Write) -> Result<()> {
+    for path in expand_inputs(inputs)? {
+        let text = read_input(&path)?;
+        for doc in scanner::split_documents(&text)? {
+            write_document(out, doc)?;
+        }
+    }
+    writeln!(out, "...")?;
+    Ok(())
+}
+
+/// Write a single document to `out` with a normalized leading `---`,
+/// dropping the document's own leading `---` marker first (if it has one) so
+/// it isn't doubled up. A document that opens with directives already has
+/// its own `---` after them, so it is written unchanged behind a `...`
+/// line instead. Shared with the split path's `--select --stdout` output,
+/// which needs the same normalization.
+pub(crate) fn write_document(out: &mut dyn Write, doc: &str) -> Result<()> {
+    if doc.starts_with('%') {
+        writeln!(out, "...")?;
+        for line in doc.lines() {
+            writeln!(out, "{}", line)?;
+        }
+        return Ok(());
+    }
+    writeln!(out, "---")?;
+    for line in strip_leading_marker(doc).lines() {
+        writeln!(out, "{}", line)?;
+    }
+    Ok(())
+}
+
+/// Drop a document's own leading `---` marker, if it has one, so joining
+/// doesn't double it up with the normalized separator we just wrote. Content
+/// on the marker's own line (`--- foo`) is kept.
+fn strip_leading_marker(doc: &str) -> &str {
+    let (first, rest) = doc.split_once('\n').unwrap_or((doc, ""));
+    if first.trim_end() == "---" {
+        rest
+    } else {
+        doc.strip_prefix("--- ").unwrap_or(doc)
+    }
+}
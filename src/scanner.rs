@@ -0,0 +1,165 @@
+/*
+* Copyright (C) 2024 Jason Mobarak
+*
+* Contact: Jason Mobarak <git@jason.mobarak.name>
+*
+* This source is subject to the license found in the file 'LICENSE' which must
+* be be distributed together with this source. All other rights reserved.
+*
+* THIS CODE AND INFORMATION IS PROVIDED "AS IS" WITHOUT WARRANTY OF ANY KIND,
+* EITHER EXPRESSED OR IMPLIED, INCLUDING BUT NOT LIMITED TO THE IMPLIED
+* WARRANTIES OF MERCHANTABILITY AND/OR FITNESS FOR A PARTICULAR PURPOSE.
+*/
+
+//! A parser-driven YAML document scanner.
+//!
+//! Unlike line-oriented matching against `^--- *$` / `^\.\.\. *$`, this runs
+//! the real YAML tokenizer over the input and only treats a `---` as a
+//! document boundary when the scanner itself says so. That means `---` or
+//! `...` appearing inside a block scalar (`script: |`) or a quoted string is
+//! never mistaken for a separator, and leading `%YAML`/`%TAG` directives are
+//! correctly attached to the document that follows them.
+
+use yaml_rust2::parser::{Event, MarkedEventReceiver, Parser};
+use yaml_rust2::scanner::Marker;
+
+use crate::Result;
+
+/// Collects the byte offset of every `DocumentStart` event the parser
+/// emits, in stream order.
+///
+/// For an *explicit* document (one with a leading `---`, optionally preceded
+/// by `%YAML`/`%TAG` directives) the parser's `DocumentStart` mark is the
+/// position of its `---`, after any directives (`document_start_offsets`
+/// moves it back over those). For an *implicit* one (no leading `---`, the
+/// overwhelmingly common case for plain YAML/Kubernetes files) the parser
+/// instead reports the mark of whatever token its one-token lookahead had
+/// already consumed by the time it emits the event — e.g. the position of
+/// the `:` after the first mapping key, not the key itself. Every other
+/// event the parser emits for that document (its first scalar, sequence, or
+/// mapping start) still carries a correct mark, and is never earlier than
+/// the document's true start, so the true start is the minimum mark seen
+/// between one `DocumentStart` and the next.
+struct BoundaryCollector {
+    starts: Vec<usize>,
+    current_min: Option<usize>,
+}
+
+impl BoundaryCollector {
+    fn finish_current(&mut self) {
+        if let Some(start) = self.current_min.take() {
+            self.starts.push(start);
+        }
+    }
+}
+
+impl MarkedEventReceiver for BoundaryCollector {
+    fn on_event(&mut self, ev: Event, mark: Marker) {
+        match ev {
+            Event::DocumentStart => {
+                self.finish_current();
+                self.current_min = Some(mark.index());
+            }
+            Event::StreamEnd => self.finish_current(),
+            _ => {
+                if let Some(start) = &mut self.current_min {
+                    *start = (*start).min(mark.index());
+                }
+            }
+        }
+    }
+}
+
+/// Scan `input` with a real YAML tokenizer and return the byte offset of
+/// each document's start, in stream order.
+///
+/// A document with no leading `---` still produces an offset (for the
+/// implicit first document), and an input with no documents at all (empty
+/// stream) returns an empty vec. Directive lines (`%YAML`, `%TAG`) directly
+/// above a document's `---` are counted as the start of that document, so
+/// slicing the input from one offset up to the next keeps them attached to
+/// the document that follows them.
+pub fn document_start_offsets(input: &str) -> Result<Vec<usize>> {
+    let mut collector = BoundaryCollector {
+        starts: Vec::new(),
+        current_min: None,
+    };
+    let mut parser = Parser::new(input.chars());
+    parser.load(&mut collector, true)?;
+    let mut starts = collector.starts;
+    for start in &mut starts {
+        // Walk back over the directive lines right above this start.
+        while let Some(above) = input[..*start].strip_suffix('\n') {
+            let line_start = above.rfind('\n').map_or(0, |i| i + 1);
+            if !input[line_start..].starts_with('%') {
+                break;
+            }
+            *start = line_start;
+        }
+    }
+    Ok(starts)
+}
+
+/// Split `input` into the raw text of each document it contains, preserving
+/// comments, indentation, and directives verbatim (the input is sliced, not
+/// re-serialized).
+pub fn split_documents(input: &str) -> Result<Vec<&str>> {
+    let starts = document_start_offsets(input)?;
+    let mut docs = Vec::with_capacity(starts.len());
+    for (i, &start) in starts.iter().enumerate() {
+        let end = starts.get(i + 1).copied().unwrap_or(input.len());
+        docs.push(&input[start..end]);
+    }
+    Ok(docs)
+}
+
+#[test]
+fn test_split_implicit_first_document_keeps_leading_key() {
+    let docs = split_documents("foo: bar\n").unwrap();
+    assert_eq!(docs, vec!["foo: bar\n"]);
+}
+
+#[test]
+fn test_split_implicit_document_with_block_scalar() {
+    let docs = split_documents("name: job1\nscript: |\n  echo hi\n").unwrap();
+    assert_eq!(docs, vec!["name: job1\nscript: |\n  echo hi\n"]);
+}
+
+#[test]
+fn test_split_explicit_documents() {
+    let docs = split_documents("foo: bar\n---\nbaz: qux\n").unwrap();
+    assert_eq!(docs, vec!["foo: bar\n", "---\nbaz: qux\n"]);
+}
+
+#[test]
+fn test_split_ignores_separator_in_block_scalar() {
+    let docs = split_documents("script: |\n  ---\n  still one doc\n").unwrap();
+    assert_eq!(docs, vec!["script: |\n  ---\n  still one doc\n"]);
+}
+
+#[test]
+fn test_split_ignores_separator_in_quoted_string() {
+    let docs = split_documents("key: \"quoted --- not a sep\"\n").unwrap();
+    assert_eq!(docs, vec!["key: \"quoted --- not a sep\"\n"]);
+}
+
+#[test]
+fn test_split_bare_document_end_marker() {
+    let docs = split_documents("foo: bar\n...\nbaz: qux\n").unwrap();
+    assert_eq!(docs, vec!["foo: bar\n...\n", "baz: qux\n"]);
+}
+
+#[test]
+fn test_split_directive_attaches_to_following_document() {
+    let docs =
+        split_documents("---\nfoo: bar\n...\n%YAML 1.1\n---\nbaz: qux\n").unwrap();
+    assert_eq!(
+        docs,
+        vec!["---\nfoo: bar\n...\n", "%YAML 1.1\n---\nbaz: qux\n"]
+    );
+}
+
+#[test]
+fn test_split_empty_stream_has_no_documents() {
+    assert_eq!(split_documents("").unwrap(), Vec::<&str>::new());
+}
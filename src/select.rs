@@ -0,0 +1,82 @@
+/*
+* Copyright (C) 2024 Jason Mobarak
+*
+* Contact: Jason Mobarak <git@jason.mobarak.name>
+*
+* This source is subject to the license found in the file 'LICENSE' which must
+* be be distributed together with this source. All other rights reserved.
+*
+* THIS CODE AND INFORMATION IS PROVIDED "AS IS" WITHOUT WARRANTY OF ANY KIND,
+* EITHER EXPRESSED OR IMPLIED, INCLUDING BUT NOT LIMITED TO THE IMPLIED
+* WARRANTIES OF MERCHANTABILITY AND/OR FITNESS FOR A PARTICULAR PURPOSE.
+*/
+
+//! `--select` predicates: choose which documents a split emits, either by
+//! position (`2..5`) or by a key/value match (`kind=Service`) resolved
+//! against the parsed document.
+
+use std::ops::Range;
+
+use serde_yaml::Value;
+
+use crate::template;
+use crate::Result;
+
+pub enum Selector {
+    Index(Range<usize>),
+    KeyValue { key: String, value: String },
+}
+
+impl Selector {
+    /// Parse a `--select` argument: `start..end` for an index range, or
+    /// `key=value` (dotted path on the left) for a field match.
+    pub fn parse(spec: &str) -> Result<Self> {
+        if let Some((start, end)) = spec.split_once("..") {
+            let start: usize = start.parse().map_err(|_| invalid(spec))?;
+            let end: usize = end.parse().map_err(|_| invalid(spec))?;
+            return Ok(Selector::Index(start..end));
+        }
+        if let Some((key, value)) = spec.split_once('=') {
+            return Ok(Selector::KeyValue {
+                key: key.to_string(),
+                value: value.to_string(),
+            });
+        }
+        Err(invalid(spec))
+    }
+
+    /// Whether the document at `index` matches this selector. Reuses the
+    /// dotted-path resolution `--name-template` uses for `{a.b.c}` tokens.
+    pub fn matches(&self, index: usize, doc: &Value) -> bool {
+        match self {
+            Selector::Index(range) => range.contains(&index),
+            Selector::KeyValue { key, value } => {
+                template::resolve_path(doc, key).as_deref() == Some(value.as_str())
+            }
+        }
+    }
+}
+
+fn invalid(spec: &str) -> Box<dyn std::error::Error> {
+    format!("invalid --select '{}': expected 'start..end' or 'key=value'", spec).into()
+}
+
+#[test]
+fn test_parse_index_range() {
+    let doc: Value = serde_yaml::from_str("kind: Service\n").unwrap();
+    let selector = Selector::parse("2..5").unwrap();
+    assert!(!selector.matches(1, &doc));
+    assert!(selector.matches(2, &doc));
+    assert!(selector.matches(4, &doc));
+    assert!(!selector.matches(5, &doc));
+}
+
+#[test]
+fn test_parse_key_value() {
+    let doc: Value = serde_yaml::from_str("kind: Service\n").unwrap();
+    let selector = Selector::parse("kind=Service").unwrap();
+    assert!(selector.matches(0, &doc));
+
+    let other: Value = serde_yaml::from_str("kind: Deployment\n").unwrap();
+    assert!(!selector.matches(0, &other));
+}
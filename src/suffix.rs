@@ -0,0 +1,105 @@
+/*
+* Copyright (C) 2024 Jason Mobarak
+*
+* Contact: Jason Mobarak <git@jason.mobarak.name>
+*
+* This source is subject to the license found in the file 'LICENSE' which must
+* be be distributed together with this source. All other rights reserved.
+*
+* THIS CODE AND INFORMATION IS PROVIDED "AS IS" WITHOUT WARRANTY OF ANY KIND,
+* EITHER EXPRESSED OR IMPLIED, INCLUDING BUT NOT LIMITED TO THE IMPLIED
+* WARRANTIES OF MERCHANTABILITY AND/OR FITNESS FOR A PARTICULAR PURPOSE.
+*/
+
+//! Suffix generators used to name split output files.
+//!
+//! `open_new_file_for_output` takes a `&mut dyn SuffixGenerator` rather than
+//! formatting an incrementing count inline, so adding a new `--suffix-style`
+//! is just adding a new implementation of this trait.
+
+/// Produces the next suffix for an output filename and advances its
+/// internal state. Suffixes are returned in the order files should be
+/// written, starting from whatever the generator was constructed with.
+pub trait SuffixGenerator {
+    fn next(&mut self) -> String;
+}
+
+/// Zero-padded decimal suffixes: `0`, `1`, ... or `001`, `002`, ... when
+/// `width` is non-zero.
+pub struct NumericSuffixGenerator {
+    next: u32,
+    width: usize,
+}
+
+impl NumericSuffixGenerator {
+    pub fn new(start: u32, width: usize) -> Self {
+        Self { next: start, width }
+    }
+}
+
+impl SuffixGenerator for NumericSuffixGenerator {
+    fn next(&mut self) -> String {
+        let suffix = format!("{:0width$}", self.next, width = self.width);
+        self.next += 1;
+        suffix
+    }
+}
+
+/// Base-26 alphabetic suffixes: `aa`, `ab`, ..., `az`, `ba`, ..., `zz`,
+/// `aaa`, ... An incrementing counter that carries over once a digit
+/// overflows `z`, growing a new leading digit rather than wrapping.
+pub struct AlphaSuffixGenerator {
+    digits: Vec<u8>,
+}
+
+impl AlphaSuffixGenerator {
+    pub fn new() -> Self {
+        Self { digits: vec![0, 0] }
+    }
+}
+
+impl Default for AlphaSuffixGenerator {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl SuffixGenerator for AlphaSuffixGenerator {
+    fn next(&mut self) -> String {
+        let suffix: String = self
+            .digits
+            .iter()
+            .map(|&d| (b'a' + d) as char)
+            .collect();
+        for digit in self.digits.iter_mut().rev() {
+            if *digit == 25 {
+                *digit = 0;
+            } else {
+                *digit += 1;
+                return suffix;
+            }
+        }
+        self.digits.insert(0, 0);
+        suffix
+    }
+}
+
+#[test]
+fn test_numeric_suffix_generator() {
+    let mut gen = NumericSuffixGenerator::new(0, 3);
+    assert_eq!(gen.next(), "000");
+    assert_eq!(gen.next(), "001");
+}
+
+#[test]
+fn test_alpha_suffix_generator_carries_over() {
+    let mut gen = AlphaSuffixGenerator::new();
+    let mut suffixes = Vec::new();
+    for _ in 0..28 {
+        suffixes.push(gen.next());
+    }
+    assert_eq!(suffixes[0], "aa");
+    assert_eq!(suffixes[25], "az");
+    assert_eq!(suffixes[26], "ba");
+    assert_eq!(suffixes[27], "bb");
+}
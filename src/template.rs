@@ -0,0 +1,104 @@
+/*
+* Copyright (C) 2024 Jason Mobarak
+*
+* Contact: Jason Mobarak <git@jason.mobarak.name>
+*
+* This source is subject to the license found in the file 'LICENSE' which must
+* be be distributed together with this source. All other rights reserved.
+*
+* THIS CODE AND INFORMATION IS PROVIDED "AS IS" WITHOUT WARRANTY OF ANY KIND,
+* EITHER EXPRESSED OR IMPLIED, INCLUDING BUT NOT LIMITED TO THE IMPLIED
+* WARRANTIES OF MERCHANTABILITY AND/OR FITNESS FOR A PARTICULAR PURPOSE.
+*/
+
+//! `--name-template` rendering: filenames built from fields inside each
+//! document, e.g. `{kind}-{metadata.name}` for Kubernetes/Helm manifests.
+
+use serde_yaml::Value;
+
+/// Render `template`, replacing each `{a.b.c}` token with the value found by
+/// walking that dotted path through `doc`'s mappings. A path that is absent,
+/// doesn't resolve to a scalar, or resolves to an empty string falls back to
+/// `index` (the document's position in the stream) so a token never renders
+/// as empty.
+pub fn render(template: &str, doc: &Value, index: usize) -> String {
+    let mut out = String::new();
+    let mut rest = template;
+    while let Some(start) = rest.find('{') {
+        out.push_str(&rest[..start]);
+        rest = &rest[start + 1..];
+        match rest.find('}') {
+            Some(end) => {
+                let path = &rest[..end];
+                out.push_str(&sanitize(&resolve_path_or_index(doc, path, index)));
+                rest = &rest[end + 1..];
+            }
+            None => {
+                // Unterminated "{", treat the rest of the template literally.
+                out.push('{');
+                break;
+            }
+        }
+    }
+    out.push_str(rest);
+    out
+}
+
+/// Walk `path` (dotted mapping keys) through `doc` and return the scalar
+/// found there, or `index` as a string if the path is missing, doesn't
+/// resolve to a scalar, or resolves to an empty string.
+fn resolve_path_or_index(doc: &Value, path: &str, index: usize) -> String {
+    resolve_path(doc, path)
+        .filter(|value| !value.is_empty())
+        .unwrap_or_else(|| index.to_string())
+}
+
+/// Walk `path` (dotted mapping keys) through `doc` and return the scalar
+/// found there, or `None` if the path is absent or doesn't resolve to a
+/// scalar. Shared with `--select key=value` matching, which needs the same
+/// dotted-path lookup without the filename-specific index fallback.
+pub fn resolve_path(doc: &Value, path: &str) -> Option<String> {
+    let mut current = doc;
+    for key in path.split('.') {
+        current = current
+            .as_mapping()
+            .and_then(|mapping| mapping.get(Value::String(key.to_string())))?;
+    }
+    scalar_to_string(current)
+}
+
+fn scalar_to_string(value: &Value) -> Option<String> {
+    match value {
+        Value::String(s) => Some(s.clone()),
+        Value::Number(n) => Some(n.to_string()),
+        Value::Bool(b) => Some(b.to_string()),
+        _ => None,
+    }
+}
+
+/// Replace characters that are unsafe in a filename (path separators and
+/// whitespace) with `_`.
+fn sanitize(value: &str) -> String {
+    value
+        .chars()
+        .map(|c| if c == '/' || c == '\\' || c.is_whitespace() { '_' } else { c })
+        .collect()
+}
+
+#[test]
+fn test_render_resolves_dotted_path() {
+    let doc: Value = serde_yaml::from_str("kind: Deployment\nmetadata:\n  name: nginx\n").unwrap();
+    assert_eq!(render("{kind}-{metadata.name}", &doc, 0), "Deployment-nginx");
+}
+
+#[test]
+fn test_render_falls_back_to_index_on_missing_path() {
+    let doc: Value = serde_yaml::from_str("kind: Deployment\n").unwrap();
+    assert_eq!(render("{metadata.name}", &doc, 3), "3");
+}
+
+#[test]
+fn test_render_sanitizes_unsafe_characters() {
+    let doc: Value = serde_yaml::from_str("metadata:\n  name: my service/v1\n").unwrap();
+    assert_eq!(render("{metadata.name}", &doc, 0), "my_service_v1");
+}
@@ -11,14 +11,29 @@
 * WARRANTIES OF MERCHANTABILITY AND/OR FITNESS FOR A PARTICULAR PURPOSE.
 */
 
+mod cli;
+mod compress;
+mod join;
+mod scanner;
+mod select;
+mod suffix;
+mod template;
+
 use std::boxed::Box;
+use std::collections::HashSet;
 use std::error::Error;
-use std::io::{self, BufRead, Write};
-use std::path::PathBuf;
+use std::io::{self, BufRead, Read, Write};
+use std::path::{Path, PathBuf};
 use std::sync::OnceLock;
 
+use clap::Parser;
 use regex::Regex;
 
+use cli::{Options, SuffixStyle};
+use compress::Compression;
+use select::Selector;
+use suffix::{AlphaSuffixGenerator, NumericSuffixGenerator, SuffixGenerator};
+
 type Result<T> = anyhow::Result<T, Box<dyn Error>>;
 
 fn regex_doc_start() -> &'static Regex {
@@ -33,74 +48,42 @@ fn regex_doc_end() -> &'static Regex {
     REGEX.get_or_init(|| Regex::new(REGEX_PAT).unwrap())
 }
 
-/// Returns a boxed reader for either stdin or the input file, also returns the
-/// basename and extension of the input file.
-fn stdin_or_input_file() -> Result<(Box<dyn io::Read>, String, String)> {
-    let mut args = std::env::args();
-    let input_filename = args.nth(1).unwrap_or_else(|| "-".to_string());
-    let input_file = match input_filename.as_str() {
+/// A resolved input stream together with the filename metadata used to
+/// name output files: the basename, the data extension, and any
+/// compression codec decoded out of a compound extension (see `compress`).
+struct InputSource {
+    reader: Box<dyn io::Read>,
+    basename: String,
+    extension: String,
+    compression: Option<Compression>,
+}
+
+/// Returns an `InputSource` for either stdin or `input_filename`,
+/// transparently decompressing it if the filename carries a compression
+/// suffix.
+fn stdin_or_input_file(input_filename: &str) -> Result<InputSource> {
+    let raw_file = match input_filename {
         "-" => Box::new(io::stdin()) as Box<dyn io::Read>,
-        _ => Box::new(std::fs::File::open(&input_filename)?) as Box<dyn io::Read>,
+        _ => Box::new(std::fs::File::open(input_filename)?) as Box<dyn io::Read>,
     };
-    let input_filename = if input_filename == "-" {
+    let display_name = if input_filename == "-" {
         "stdin.yaml".to_string()
     } else {
-        input_filename
-    };
-    let (basename, extension) = basename(PathBuf::from(input_filename));
-    Ok((input_file, basename, extension))
-}
-
-/// Get the basename of a file without the extension.  Includes
-/// any leading path components.  The extension is defined as anything
-/// after the last "." in the filename.  Also returns the extension;
-fn basename(filename: PathBuf) -> (String, String) {
-    let dirname = filename.parent().unwrap().to_str().unwrap();
-    let extension = filename.extension();
-    let extension = extension.unwrap_or_default().to_str().unwrap().to_string();
-    let dirname = if dirname.is_empty() {
-        "".to_string()
-    } else {
-        dirname.to_string() + "/"
+        input_filename.to_string()
     };
-    let filename = filename.file_name().unwrap().to_str().unwrap();
-    // Get everything before the last ".".
-    let split = filename.rsplit_once('.');
-    if let Some((basename, _)) = split {
-        (dirname + basename, extension)
-    } else {
-        (dirname + filename, extension)
-    }
+    let path = PathBuf::from(display_name);
+    let file_name = path.file_name().unwrap().to_str().unwrap();
+    let (basename, extension, compression) = compress::split_compound_extension(file_name);
+    let reader = compress::decode_reader(compression, raw_file);
+    Ok(InputSource {
+        reader,
+        basename,
+        extension,
+        compression,
+    })
 }
 
-#[test]
-fn test_basename() {
-    assert_eq!(
-        basename(PathBuf::from("foo")),
-        ("foo".to_string(), "".to_string())
-    );
-    assert_eq!(
-        basename(PathBuf::from("foo.yaml")),
-        ("foo".to_string(), "yaml".to_string())
-    );
-    assert_eq!(
-        basename(PathBuf::from("foo.bar.yaml")),
-        ("foo.bar".to_string(), "yaml".to_string())
-    );
-    assert_eq!(
-        basename(PathBuf::from("foo.bar.baz.yaml")),
-        ("foo.bar.baz".to_string(), "yaml".to_string())
-    );
-    assert_eq!(
-        basename(PathBuf::from("dir/foo.bar.baz.yaml")),
-        ("dir/foo.bar.baz".to_string(), "yaml".to_string())
-    );
-}
-
-fn output_line_to_file(
-    line: &str,
-    output_file: &mut Option<io::BufWriter<std::fs::File>>,
-) -> Result<()> {
+fn output_line_to_file(line: &str, output_file: &mut Option<Box<dyn Write>>) -> Result<()> {
     if let Some(output_file) = output_file {
         output_file.write_all(line.as_bytes())?;
         output_file.write_all(b"\n")?;
@@ -109,49 +92,88 @@ fn output_line_to_file(
 }
 
 fn open_new_file_for_output(
-    basename: &str,
+    outdir: &Path,
+    prefix: &str,
     extension: &str,
-    output_file_count: &mut u32,
-) -> Result<io::BufWriter<std::fs::File>> {
-    let output_filename = format!("{}-{}.{}", basename, output_file_count, extension);
+    compression: Option<Compression>,
+    suffix_gen: &mut dyn SuffixGenerator,
+) -> Result<Box<dyn Write>> {
+    let suffix = suffix_gen.next();
+    let full_extension = compress::full_extension(extension, compression);
+    let output_filename = outdir.join(format!("{}-{}.{}", prefix, suffix, full_extension));
     let output_file = std::fs::File::create(output_filename)?;
-    *output_file_count += 1;
-    Ok(io::BufWriter::new(output_file))
+    Ok(compress::encode_writer(
+        compression,
+        Box::new(io::BufWriter::new(output_file)),
+    ))
 }
 
-fn main() -> Result<()> {
-    /*
-       Open a file from the command line and splits the YAML documents into
-       separate files based on the appearance of the YAML document separator
-       "---".
+/// The directory output files go into when `--outdir` isn't given: the
+/// input file's own parent directory, or `.` for stdin (no directory to
+/// derive a default from).
+fn default_outdir(input_filename: &str) -> PathBuf {
+    if input_filename == "-" {
+        return PathBuf::from(".");
+    }
+    match Path::new(input_filename).parent() {
+        Some(parent) if !parent.as_os_str().is_empty() => parent.to_path_buf(),
+        _ => PathBuf::from("."),
+    }
+}
 
-       Each file will be named after the basename of the input file with a
-       numeric suffix.  For example, if the input file is named "foo.yaml", the
-       output files will be named "foo-1.yaml".
+fn make_suffix_generator(style: SuffixStyle, start: u32, width: usize) -> Box<dyn SuffixGenerator> {
+    match style {
+        SuffixStyle::Numeric => Box::new(NumericSuffixGenerator::new(start, width)),
+        SuffixStyle::Alpha => Box::new(AlphaSuffixGenerator::new()),
+    }
+}
 
-       For the first document, the "---" separator is optional.  If it is
-       omitted, the first document number will default to "1". For example, if
-       the input file is named "foo.yaml", the output file will be named
-       "foo-1.yaml".
-    */
-    // println!("1");
-    let (input_file, basename, extension) = stdin_or_input_file()?;
-    // println!("2");
-    // let mut input = io::BufReader::new(input_file);
+/// Open an output file named `{rendered}.{extension}` under `outdir`,
+/// disambiguating a collision with an already-used rendered name by
+/// appending a numeric suffix (`foo`, `foo-1`, `foo-2`, ...).
+fn open_templated_file_for_output(
+    outdir: &Path,
+    rendered: &str,
+    extension: &str,
+    compression: Option<Compression>,
+    used_names: &mut HashSet<String>,
+) -> Result<Box<dyn Write>> {
+    let mut name = rendered.to_string();
+    let mut attempt = 0u32;
+    while used_names.contains(&name) {
+        attempt += 1;
+        name = format!("{}-{}", rendered, attempt);
+    }
+    used_names.insert(name.clone());
+    let full_extension = compress::full_extension(extension, compression);
+    let output_filename = outdir.join(format!("{}.{}", name, full_extension));
+    let output_file = std::fs::File::create(output_filename)?;
+    Ok(compress::encode_writer(
+        compression,
+        Box::new(io::BufWriter::new(output_file)),
+    ))
+}
+
+/// Split `input` into separate files using the old line-regex matcher,
+/// treating any line matching `^--- *$` / `^\.\.\. *$` as a boundary. Kept
+/// around behind `--naive` for byte-for-byte backward compatibility, since it
+/// mishandles separators that appear inside block scalars or quoted strings.
+fn run_naive(
+    input_file: Box<dyn io::Read>,
+    outdir: &Path,
+    prefix: &str,
+    extension: &str,
+    compression: Option<Compression>,
+    suffix_gen: &mut dyn SuffixGenerator,
+) -> Result<()> {
     let input = io::BufReader::new(input_file);
-    // println!("3");
-    let mut output_file_count = 0;
-    // println!("4");
     let mut output_file = None;
-    // let buf: &mut String = &mut String::new();
     for line in input.lines() {
         let line = line.unwrap();
         if regex_doc_start().is_match(&line) {
             // Start of a new document, open a new output file.
             output_file = Some(open_new_file_for_output(
-                &basename,
-                &extension,
-                &mut output_file_count,
+                outdir, prefix, extension, compression, suffix_gen,
             )?);
         } else if regex_doc_end().is_match(&line) {
             // End of a document, close the current output file.
@@ -160,15 +182,165 @@ fn main() -> Result<()> {
             // Write the line to the output file.
             if output_file.is_none() {
                 output_file = Some(open_new_file_for_output(
-                    &basename,
-                    &extension,
-                    &mut output_file_count,
+                    outdir, prefix, extension, compression, suffix_gen,
                 )?);
             }
             output_line_to_file(&line, &mut output_file)?;
         }
     }
-    /*
-     */
     Ok(())
 }
+
+/// Split `input` into separate files using the real YAML scanner (see
+/// `scanner`), slicing the original bytes at each document boundary so
+/// comments, indentation, and directives are preserved verbatim.
+///
+/// When `name_template` is given, each document is additionally parsed into
+/// a `serde_yaml::Value` so the template can be rendered against it (see
+/// `template`); otherwise files keep the numeric/alpha suffix behavior.
+///
+/// When `selector` is given, each document is parsed the same way and
+/// skipped entirely (no file opened, no suffix consumed) unless it matches
+/// (see `select`); with `stdout_out` given, matched documents are written
+/// there (normalized through `join::write_document`) instead of a file.
+#[allow(clippy::too_many_arguments)]
+fn run_scanner(
+    mut input_file: Box<dyn io::Read>,
+    outdir: &Path,
+    prefix: &str,
+    extension: &str,
+    compression: Option<Compression>,
+    suffix_gen: &mut dyn SuffixGenerator,
+    name_template: Option<&str>,
+    selector: Option<&Selector>,
+    mut stdout_out: Option<&mut dyn Write>,
+) -> Result<()> {
+    let mut text = String::new();
+    input_file.read_to_string(&mut text)?;
+
+    let mut used_names = HashSet::new();
+    for (index, doc) in scanner::split_documents(&text)?.into_iter().enumerate() {
+        let value = if name_template.is_some() || selector.is_some() {
+            serde_yaml::from_str(doc).unwrap_or(serde_yaml::Value::Null)
+        } else {
+            serde_yaml::Value::Null
+        };
+
+        if let Some(selector) = selector {
+            if !selector.matches(index, &value) {
+                continue;
+            }
+        }
+
+        if let Some(out) = &mut stdout_out {
+            join::write_document(*out, doc)?;
+            continue;
+        }
+
+        let mut output_file = Some(match name_template {
+            Some(tmpl) => {
+                let rendered = template::render(tmpl, &value, index);
+                open_templated_file_for_output(
+                    outdir,
+                    &rendered,
+                    extension,
+                    compression,
+                    &mut used_names,
+                )?
+            }
+            None => open_new_file_for_output(outdir, prefix, extension, compression, suffix_gen)?,
+        });
+        for line in doc.lines() {
+            output_line_to_file(line, &mut output_file)?;
+        }
+    }
+    Ok(())
+}
+
+fn main() -> Result<()> {
+    /*
+       Open a file from the command line and splits the YAML documents into
+       separate files based on the appearance of real YAML document
+       boundaries (or, with `--naive`, the literal "---"/"..." separator
+       lines).
+
+       Each file is named `{prefix}-{suffix}.{ext}`, written into `--outdir`
+       (the input file's own directory by default, so output lands next to
+       it rather than in the caller's working directory). `prefix` is the
+       input file's basename unless overridden with `--prefix`, and
+       `suffix` is produced by a `SuffixGenerator` chosen via
+       `--suffix-style` (`numeric`, the default, or `alpha`). If the input
+       carries a compression suffix (`.gz`, `.bz2`, `.zst`) it is decoded
+       transparently, and each output file is re-encoded with the same
+       codec. With `--name-template`, files are instead named
+       `{rendered}.{ext}` from fields inside each document (see `template`).
+
+       `--select` narrows which documents get a file at all (see `select`),
+       and `--join` skips splitting entirely to run the inverse: it merges
+       its input file(s) back into a single stream on stdout (see `join`).
+    */
+    let opts = Options::parse();
+
+    if opts.join {
+        let stdout = io::stdout();
+        return join::run_join(&opts.input, &mut stdout.lock());
+    }
+
+    let selector = opts.select.as_deref().map(Selector::parse).transpose()?;
+
+    let input = stdin_or_input_file(&opts.input[0])?;
+    let prefix = opts.prefix.unwrap_or(input.basename);
+    let outdir = opts
+        .outdir
+        .unwrap_or_else(|| default_outdir(&opts.input[0]));
+    let mut suffix_gen = make_suffix_generator(opts.suffix_style, opts.start, opts.width);
+
+    if opts.naive {
+        run_naive(
+            input.reader,
+            &outdir,
+            &prefix,
+            &input.extension,
+            input.compression,
+            suffix_gen.as_mut(),
+        )
+    } else {
+        let stdout = io::stdout();
+        let mut stdout = stdout.lock();
+        run_scanner(
+            input.reader,
+            &outdir,
+            &prefix,
+            &input.extension,
+            input.compression,
+            suffix_gen.as_mut(),
+            opts.name_template.as_deref(),
+            selector.as_ref(),
+            opts.stdout.then_some(&mut stdout as &mut dyn Write),
+        )
+    }
+}
+
+#[test]
+fn test_run_scanner_select_stdout_does_not_duplicate_separator_for_non_first_doc() {
+    let input = "foo: bar\n---\nkind: Service\nmetadata:\n  name: nginx\n";
+    let selector = Selector::parse("kind=Service").unwrap();
+    let mut suffix_gen = make_suffix_generator(SuffixStyle::Numeric, 0, 0);
+    let mut out: Vec<u8> = Vec::new();
+    run_scanner(
+        Box::new(io::Cursor::new(input.as_bytes().to_vec())) as Box<dyn io::Read>,
+        Path::new("."),
+        "prefix",
+        "yaml",
+        None,
+        suffix_gen.as_mut(),
+        None,
+        Some(&selector),
+        Some(&mut out as &mut dyn Write),
+    )
+    .unwrap();
+    assert_eq!(
+        String::from_utf8(out).unwrap(),
+        "---\nkind: Service\nmetadata:\n  name: nginx\n"
+    );
+}